@@ -1,61 +1,183 @@
-//! # Rust コンテナ・ベンチマーク
-//!
-//! C++版の簡易ベンチマークを **標準ライブラリのみ** で Rust に移植した実装。
-//!
-//! - 対象コンテナ: `Vec`, `VecDeque`, `LinkedList`
-//! - 計測内容: データコピー性能・シーケンシャル読み取り・平均/分散（母分散）
-//! - 設計方針: イテレータ中心／各ベンチケースは新規コンテナで独立測定／RAII による計測
-//! - 外部クレート: 不要（擬似乱数は MT19937 の簡易実装）
-//!
-//! 実行は単一ファイル `main.rs` で可能。
-
-use std::collections::{LinkedList, VecDeque};
-use std::fmt::{Display, Write};
-use std::hint::black_box;
-use std::time::Instant;
-
-/// ベンチマークの設定値をまとめたモジュール。
-mod config {
-    /// ベンチマークで扱うデータ型。
-    pub type DataType = i32;
-    /// 元データの要素数。
-    pub const ELEMENT_COUNT: usize = 1_000_000;
-    /// シーケンシャル読み取りの繰り返し回数。
-    pub const READ_REPEAT_COUNT: usize = 10;
-    /// 先頭表示件数。
-    pub const DISPLAY_COUNT: usize = 10;
-    /// 乱数の最小値（含む）。
-    pub const RANDOM_MIN: DataType = -100;
-    /// 乱数の最大値（含む）。
-    pub const RANDOM_MAX: DataType = 100;
-}
-
-/// スコープ生存期間で経過時間を測定し、ドロップ時に表示する簡易プロファイラ。
-///
-/// # 使い方
-/// スコープ先頭でインスタンスを生成すると、スコープ終了時（`Drop`）に経過時間が出力される。
-#[must_use]
-struct ScopeProfiler {
-    /// 計測対象のラベル。
-    mark: String,
-    /// 計測開始時刻。
-    start: Instant,
-}
-
-impl ScopeProfiler {
-    /// 指定ラベルで計測を開始する。
-    pub fn new(mark: impl Into<String>) -> Self {
-        Self { mark: mark.into(), start: Instant::now() }
-    }
-}
-
-impl Drop for ScopeProfiler {
-    fn drop(&mut self) {
-        let ms = self.start.elapsed().as_secs_f64() * 1000.0;
-        println!("実行時間 ({}): {:.2} ms", self.mark, ms);
-    }
-}
-
+//! # Rust コンテナ・ベンチマーク
+//!
+//! C++版の簡易ベンチマークを **標準ライブラリのみ** で Rust に移植した実装。
+//!
+//! - 対象コンテナ: `Vec`, `VecDeque`, `LinkedList`, `BTreeMap`, `HashMap`, `Matrix`（2次元配置）
+//! - 計測内容: データコピー性能・シーケンシャル読み取り・平均/分散（母分散）・連想コンテナの挿入/検索・2次元走査のキャッシュ局所性
+//! - 設計方針: イテレータ中心／各ベンチケースは新規コンテナで独立測定／統計的ベンチマーク（`bench`）による多サンプル計測
+//! - 外部クレート: 不要（擬似乱数は MT19937 / Xoshiro256** の自前実装）
+//! - 出力形式: 既定は日本語の人間向けテキスト。`--format=csv` / `--format=json` で計測結果を機械可読に出力する。
+//!
+//! 実行は単一ファイル `main.rs` で可能。
+
+use std::collections::{BTreeMap, HashMap, LinkedList, VecDeque};
+use std::fmt::{Display, Write};
+use std::hint::black_box;
+use std::ops::{Index, IndexMut};
+use std::time::Instant;
+
+/// ベンチマークの設定値をまとめたモジュール。
+mod config {
+    /// ベンチマークで扱うデータ型。
+    pub type DataType = i32;
+    /// 元データの要素数。
+    pub const ELEMENT_COUNT: usize = 1_000_000;
+    /// シーケンシャル読み取りの繰り返し回数。
+    pub const READ_REPEAT_COUNT: usize = 10;
+    /// 先頭表示件数。
+    pub const DISPLAY_COUNT: usize = 10;
+    /// 乱数の最小値（含む）。
+    pub const RANDOM_MIN: DataType = -100;
+    /// 乱数の最大値（含む）。
+    pub const RANDOM_MAX: DataType = 100;
+    /// 統計的ベンチマークの計測回数（ウォームアップを除く）。
+    pub const BENCH_ITERS: usize = 20;
+    /// 行列ベンチマークの行数。
+    pub const MATRIX_ROWS: usize = 1_000;
+    /// 行列ベンチマークの列数。
+    pub const MATRIX_COLS: usize = 1_000;
+}
+
+/// 出力形式。`--format=csv|json` で切り替える。既定は人間向けテキスト。
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// 日本語の人間向けテキスト（既定）。
+    Text,
+    /// CSV（1 行 1 ケース）。
+    Csv,
+    /// JSON 配列（1 要素 1 ケース）。
+    Json,
+}
+
+/// コマンドライン引数 `--format=csv|json` を解析する。指定がなければ `Text`。
+///
+/// 値が `csv`/`json` のどちらでもない場合は警告を標準エラーに出力し、`Text` にフォールバックする。
+fn parse_output_format() -> OutputFormat {
+    for arg in std::env::args().skip(1) {
+        if let Some(value) = arg.strip_prefix("--format=") {
+            return match value {
+                "csv" => OutputFormat::Csv,
+                "json" => OutputFormat::Json,
+                other => {
+                    eprintln!("警告: 不明な --format 値 '{}' です。テキスト出力にフォールバックします。", other);
+                    OutputFormat::Text
+                }
+            };
+        }
+    }
+    OutputFormat::Text
+}
+
+/// 1 ベンチマークケース分の統計結果（機械可読出力のレコード）。
+struct BenchRecord {
+    /// ケースの分類（例: `data_copy`, `map_insert`）。
+    category: String,
+    /// コンテナ名（例: `Vec`, `BTreeMap`）。
+    container: String,
+    /// ケースの見出し（表示用ラベル）。
+    label: String,
+    /// 計測回数。
+    samples: usize,
+    /// 最小値（ミリ秒）。
+    min_ms: f64,
+    /// 中央値（ミリ秒）。
+    median_ms: f64,
+    /// 平均値（ミリ秒）。
+    mean_ms: f64,
+    /// 標本標準偏差（ミリ秒）。
+    stddev_ms: f64,
+}
+
+/// `run()` 全体を通して共有する実行コンテキスト。
+///
+/// 出力形式に応じて人間向けテキストの表示可否を切り替えつつ、
+/// 機械可読出力用のベンチマーク結果を蓄積する。
+struct RunContext {
+    /// 出力形式。
+    format: OutputFormat,
+    /// 蓄積されたベンチマーク結果。
+    records: Vec<BenchRecord>,
+}
+
+impl RunContext {
+    /// 指定した出力形式でコンテキストを初期化する。
+    fn new(format: OutputFormat) -> Self {
+        Self { format, records: Vec::new() }
+    }
+
+    /// 人間向けの進捗・結果表示。`Text` 形式の時のみ出力する。
+    fn logln(&self, line: impl AsRef<str>) {
+        if self.format == OutputFormat::Text {
+            println!("{}", line.as_ref());
+        }
+    }
+
+    /// 蓄積済みのベンチマーク結果を、設定済みの出力形式で書き出す。
+    fn emit_records(&self) {
+        match self.format {
+            OutputFormat::Text => {}
+            OutputFormat::Csv => self.emit_csv(),
+            OutputFormat::Json => self.emit_json(),
+        }
+    }
+
+    /// CSV（ヘッダ + 1 行 1 ケース）で出力する。
+    fn emit_csv(&self) {
+        println!("category,container,label,samples,min_ms,median_ms,mean_ms,stddev_ms");
+        for r in &self.records {
+            println!(
+                "{},{},{},{},{:.6},{:.6},{:.6},{:.6}",
+                r.category, r.container, r.label, r.samples, r.min_ms, r.median_ms, r.mean_ms, r.stddev_ms
+            );
+        }
+    }
+
+    /// JSON 配列（1 要素 1 ケース）で出力する。
+    fn emit_json(&self) {
+        println!("[");
+        for (i, r) in self.records.iter().enumerate() {
+            let comma = if i + 1 < self.records.len() { "," } else { "" };
+            println!(
+                "  {{\"category\": \"{}\", \"container\": \"{}\", \"label\": \"{}\", \"samples\": {}, \"min_ms\": {:.6}, \"median_ms\": {:.6}, \"mean_ms\": {:.6}, \"stddev_ms\": {:.6}}}{}",
+                r.category, r.container, r.label, r.samples, r.min_ms, r.median_ms, r.mean_ms, r.stddev_ms, comma
+            );
+        }
+        println!("]");
+    }
+}
+
+/// スコープ生存期間で経過時間を測定し、ドロップ時に表示する簡易プロファイラ。
+///
+/// # 使い方
+/// スコープ先頭でインスタンスを生成すると、スコープ終了時（`Drop`）に経過時間が出力される。
+/// `verbose` が `false` の場合は何も出力しない。
+#[must_use]
+struct ScopeProfiler {
+    /// 計測対象のラベル。
+    mark: String,
+    /// 計測開始時刻。
+    start: Instant,
+    /// 表示の有無（機械可読出力モードでは抑止する）。
+    verbose: bool,
+}
+
+impl ScopeProfiler {
+    /// 指定ラベル・表示可否で計測を開始する。
+    pub fn with_verbosity(mark: impl Into<String>, verbose: bool) -> Self {
+        Self { mark: mark.into(), start: Instant::now(), verbose }
+    }
+}
+
+impl Drop for ScopeProfiler {
+    fn drop(&mut self) {
+        if !self.verbose {
+            return;
+        }
+        let ms = self.start.elapsed().as_secs_f64() * 1000.0;
+        println!("実行時間 ({}): {:.2} ms", self.mark, ms);
+    }
+}
+
 /// MT19937 による擬似乱数生成器（外部依存なし）。
 ///
 /// 32bit のメルセンヌツイスタをそのまま移植し、整数範囲の一様乱数を提供する。
@@ -125,10 +247,97 @@ impl Mt19937 {
     pub fn next_u64(&mut self) -> u64 {
         ((self.next_u32() as u64) << 32) | self.next_u32() as u64
     }
+}
+
+impl RandomSource for Mt19937 {
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        Mt19937::next_u64(self)
+    }
+}
+
+/// SplitMix64 による擬似乱数生成器。
+///
+/// Xoshiro256** のシード展開専用の簡易生成器で、単体では使用しない。
+struct SplitMix64 {
+    /// 内部状態。
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// シードから生成器を初期化する。
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// 次の 64bit 値を生成する。
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
 
-    /// `[min, max]` の一様整数を生成する。
+/// Xoshiro256** による擬似乱数生成器（外部依存なし）。
+///
+/// 256bit の内部状態を持つ高速な生成器。Mt19937 よりも短い周期だが、
+/// 一般的な用途では十分な品質と速度を両立する。
+struct Xoshiro256StarStar {
+    /// 内部状態（4 ワード）。
+    s: [u64; 4],
+}
+
+impl Xoshiro256StarStar {
+    /// シードから生成器を初期化する。SplitMix64 で内部状態を展開する。
+    pub fn new(seed: u64) -> Self {
+        let mut sm = SplitMix64::new(seed);
+        let s = [sm.next_u64(), sm.next_u64(), sm.next_u64(), sm.next_u64()];
+        Self { s }
+    }
+
+    /// 左ビットローテーション。
     #[inline]
-    pub fn next_i32_range(&mut self, min: i32, max: i32) -> i32 {
+    fn rotl(x: u64, k: u32) -> u64 {
+        x.rotate_left(k)
+    }
+
+    /// 次の 64bit 値を生成する。
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        let result = Self::rotl(self.s[1].wrapping_mul(5), 7).wrapping_mul(9);
+
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = Self::rotl(self.s[3], 45);
+
+        result
+    }
+}
+
+impl RandomSource for Xoshiro256StarStar {
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        Xoshiro256StarStar::next_u64(self)
+    }
+}
+
+/// 擬似乱数生成器を抽象化するトレイト。
+///
+/// 生成バックエンド（`Mt19937` / `Xoshiro256StarStar`）を差し替えて
+/// 乱数生成コスト自体をベンチマーク対象にできるようにする。
+trait RandomSource {
+    /// 次の 64bit 値を生成する。
+    fn next_u64(&mut self) -> u64;
+
+    /// `[min, max]` の一様整数を棄却法で生成する。
+    #[inline]
+    fn next_i32_range(&mut self, min: i32, max: i32) -> i32 {
         debug_assert!(min <= max);
         let span = (max as i64 - min as i64 + 1) as u64;
         debug_assert!(span > 0);
@@ -144,188 +353,509 @@ impl Mt19937 {
         }
     }
 }
-
-/// 指定サイズの乱数ベクタを生成する。
-///
-/// # 引数
-/// - `size`: 生成する要素数
-/// - `min_v`, `max_v`: 乱数範囲（両端含む）
-///
-/// # 戻り値
-/// 乱数で埋めた `Vec<i32>`。
-fn generate_source(size: usize, min_v: i32, max_v: i32) -> Vec<i32> {
-    let seed = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos() as u64;
-
-    let _profiler = ScopeProfiler::new("乱数配列生成");
+
+/// 行優先（row-major）で要素を平坦な `Vec` に格納する固定サイズの2次元配列。
+///
+/// `Vec<Vec<T>>` と異なり行ごとの個別ヒープ確保がなく、連続領域としてアクセスできる。
+/// フィールドはタプルで `(データ本体, 列数)`。
+struct Matrix<T>(Vec<T>, usize);
+
+impl<T: Clone> Matrix<T> {
+    /// `rows × cols` の行列を `value` で埋めて生成する。
+    fn filled(rows: usize, cols: usize, value: T) -> Self {
+        Self(vec![value; rows * cols], cols)
+    }
+}
+
+impl<T> Index<usize> for Matrix<T> {
+    type Output = [T];
+
+    /// `row` 行目をスライスとして返す。
+    fn index(&self, row: usize) -> &[T] {
+        let stride = self.1;
+        &self.0[row * stride..][..stride]
+    }
+}
+
+impl<T> IndexMut<usize> for Matrix<T> {
+    /// `row` 行目を可変スライスとして返す。
+    fn index_mut(&mut self, row: usize) -> &mut [T] {
+        let stride = self.1;
+        &mut self.0[row * stride..][..stride]
+    }
+}
+
+/// 指定サイズの乱数ベクタを生成する。
+///
+/// # 引数
+/// - `size`: 生成する要素数
+/// - `min_v`, `max_v`: 乱数範囲（両端含む）
+/// - `verbose`: 生成時間の表示可否
+///
+/// # 戻り値
+/// 乱数で埋めた `Vec<i32>`。
+fn generate_source(size: usize, min_v: i32, max_v: i32, verbose: bool) -> Vec<i32> {
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+
+    let _profiler = ScopeProfiler::with_verbosity("乱数配列生成", verbose);
+    let mut rng = Mt19937::new(seed);
+
+    generate_with(&mut rng, size, min_v, max_v)
+}
+
+/// 任意の `RandomSource` バックエンドで乱数ベクタを生成する。
+///
+/// # 引数
+/// - `rng`: 乱数生成器（`Mt19937` / `Xoshiro256StarStar` など）
+/// - `size`: 生成する要素数
+/// - `min_v`, `max_v`: 乱数範囲（両端含む）
+fn generate_with<R: RandomSource + ?Sized>(rng: &mut R, size: usize, min_v: i32, max_v: i32) -> Vec<i32> {
+    (0..size).map(|_| rng.next_i32_range(min_v, max_v)).collect()
+}
+
+/// 連想コンテナ向けの乱数キー列を生成する（`i32` の全域から一様に抽出）。
+///
+/// # 引数
+/// - `size`: 生成するキー数
+fn generate_keys_random(size: usize) -> Vec<i32> {
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
     let mut rng = Mt19937::new(seed);
-
-    (0..size).map(|_| rng.next_i32_range(min_v, max_v)).collect()
-}
-
-/// 先頭 `n` 要素を 1 行で出力する（スペース区切り）。
-///
-/// # 引数
-/// - `name`: 見出し名（コンテナ名）
-/// - `iter`: 対象イテレータ
-/// - `n`: 表示件数
-fn print_first_n<I, T>(name: &str, iter: I, n: usize)
-where
-    I: IntoIterator<Item = T>,
-    T: Display,
-{
-    let mut buf = String::new();
-    iter.into_iter().take(n).for_each(|x| {
-        let _ = write!(buf, "{} ", x);
-    });
-    println!("{}: {}", name, buf.trim_end());
-}
-
-/// 平均値（母平均）を求める。空入力時は `0.0`。
-///
-/// # 計算量
-/// O(N) で 1 パス。
-fn mean<I>(iter: I) -> f64
-where
-    I: IntoIterator<Item = i32>,
-{
-    let (sum, cnt) = iter.into_iter().fold((0f64, 0f64), |(s, c), v| (s + v as f64, c + 1.0));
-    if cnt == 0.0 { 0.0 } else { sum / cnt }
-}
-
-/// 分散（母分散）を 1 パスで求める（Welford 法）。空入力時は `0.0`。
-///
-/// # 計算量
-/// O(N) で 1 パス、数値安定性も高い。
-fn variance<I>(iter: I) -> f64
-where
-    I: IntoIterator<Item = i32>,
-{
-    let mut n = 0f64;
-    let mut mean = 0f64;
-    let mut m2 = 0f64;
-    for x in iter {
-        n += 1.0;
-        let dx = x as f64 - mean;
-        mean += dx / n;
-        m2 += dx * (x as f64 - mean);
-    }
-    if n == 0.0 { 0.0 } else { m2 / n }
-}
-
-/// イテレータのシーケンシャル読み取りを `repeats` 回行い、合計値を返す。
-///
-/// 最適化抑止は行わず、素直に加算するのみ。
-fn read_sequential<I>(it: I, repeats: usize) -> i64
-where
-    I: Clone + IntoIterator<Item = i32>,
-{
-    std::iter::repeat(())
-        .take(repeats)
-        .map(|_| it.clone().into_iter().map(|v| v as i64).sum::<i64>())
-        .sum()
-}
-
-/// ベンチマーク本体。各処理を独立に測定・出力する。
-fn run() {
-    use config::*;
-
-    println!("===== Rust コンテナ・ベンチマーク =====");
-    println!("要素数: {}\n", ELEMENT_COUNT);
-
-    // 元データ生成
-    println!("● 元データ生成");
-    let src = generate_source(ELEMENT_COUNT, RANDOM_MIN, RANDOM_MAX);
-
-    // --- データコピー性能（ケースごとに新しいコンテナを生成） ---
-    println!("\n● データコピー性能");
-    {
-        let _profiler = ScopeProfiler::new("Vec_reserveなし");
-        let mut v: Vec<DataType> = Vec::new();
-        v.extend_from_slice(&src);
-    }
-    {
-        let _profiler = ScopeProfiler::new("Vec_reserveあり");
-        let mut v: Vec<DataType> = Vec::with_capacity(ELEMENT_COUNT);
-        v.extend_from_slice(&src);
-    }
-    {
-        let _profiler = ScopeProfiler::new("VecDeque");
-        let mut d: VecDeque<DataType> = VecDeque::with_capacity(ELEMENT_COUNT);
-        d.extend(src.iter().copied());
-    }
-    {
-        let _profiler = ScopeProfiler::new("LinkedList");
-        let mut l: LinkedList<DataType> = LinkedList::new();
-        l.extend(src.iter().copied());
-    }
-
-    // --- 以降の処理（読み取り・統計）用に、計測対象外でコンテナを準備 ---
-    let vec_main: Vec<DataType> = src.clone();
-    let deq_main: VecDeque<DataType> = src.iter().copied().collect();
-    let lis_main: LinkedList<DataType> = src.iter().copied().collect();
-
-    // --- シーケンシャル読み取り ---
-    println!("\n● シーケンシャル読み取り性能 ({}回繰り返し)", READ_REPEAT_COUNT);
-    {
-        let _profiler = ScopeProfiler::new("Vec");
-        let sum = read_sequential(vec_main.iter().copied(), READ_REPEAT_COUNT);
-        black_box(sum);
-    }
-    {
-        let _profiler = ScopeProfiler::new("VecDeque");
-        let sum = read_sequential(deq_main.iter().copied(), READ_REPEAT_COUNT);
-        black_box(sum);
-    }
-    {
-        let _profiler = ScopeProfiler::new("LinkedList");
-        let sum = read_sequential(lis_main.iter().copied(), READ_REPEAT_COUNT);
-        black_box(sum);
-    }
-
-    // --- 先頭確認 ---
-    println!("\n● 先頭 {} 要素の確認", DISPLAY_COUNT);
-    print_first_n("Vec", vec_main.iter().copied(), DISPLAY_COUNT);
-    print_first_n("VecDeque", deq_main.iter().copied(), DISPLAY_COUNT);
-    print_first_n("LinkedList", lis_main.iter().copied(), DISPLAY_COUNT);
-
-    // --- 平均 ---
-    println!("\n● 平均値計算の性能");
-    {
-        let _profiler = ScopeProfiler::new("Vec_平均値");
-        println!("Vecの平均値: {:.3}", mean(vec_main.iter().copied()));
-    }
-    {
-        let _profiler = ScopeProfiler::new("VecDeque_平均値");
-        println!("VecDequeの平均値: {:.3}", mean(deq_main.iter().copied()));
-    }
-    {
-        let _profiler = ScopeProfiler::new("LinkedList_平均値");
-        println!("LinkedListの平均値: {:.3}", mean(lis_main.iter().copied()));
-    }
-
-    // --- 分散 ---
-    println!("\n● 分散計算の性能");
-    {
-        let _profiler = ScopeProfiler::new("Vec_分散");
-        println!("Vecの分散: {:.1}", variance(vec_main.iter().copied()));
-    }
-    {
-        let _profiler = ScopeProfiler::new("VecDeque_分散");
-        println!("VecDequeの分散: {:.1}", variance(deq_main.iter().copied()));
-    }
-    {
-        let _profiler = ScopeProfiler::new("LinkedList_分散");
-        println!("LinkedListの分散: {:.1}", variance(lis_main.iter().copied()));
-    }
-
-    println!("\n===== ベンチマーク終了 =====");
-}
-
-/// エントリポイント。
-fn main() {
-    let _profiler = ScopeProfiler::new("全体処理");
-    run();
-}
+    (0..size).map(|_| rng.next_i32_range(i32::MIN, i32::MAX)).collect()
+}
+
+/// 連想コンテナ向けの昇順キー列を生成する（`0..size`）。
+///
+/// # 引数
+/// - `size`: 生成するキー数
+fn generate_keys_sequential(size: usize) -> Vec<i32> {
+    (0..size as i32).collect()
+}
+
+/// 先頭 `n` 要素を 1 行で出力する（スペース区切り）。
+///
+/// # 引数
+/// - `name`: 見出し名（コンテナ名）
+/// - `iter`: 対象イテレータ
+/// - `n`: 表示件数
+fn print_first_n<I, T>(name: &str, iter: I, n: usize)
+where
+    I: IntoIterator<Item = T>,
+    T: Display,
+{
+    let mut buf = String::new();
+    iter.into_iter().take(n).for_each(|x| {
+        let _ = write!(buf, "{} ", x);
+    });
+    println!("{}: {}", name, buf.trim_end());
+}
+
+/// 平均値（母平均）を求める。空入力時は `0.0`。
+///
+/// # 計算量
+/// O(N) で 1 パス。
+fn mean<I>(iter: I) -> f64
+where
+    I: IntoIterator<Item = f64>,
+{
+    let (sum, cnt) = iter.into_iter().fold((0f64, 0f64), |(s, c), v| (s + v, c + 1.0));
+    if cnt == 0.0 { 0.0 } else { sum / cnt }
+}
+
+/// 分散を 1 パスで求める（Welford 法）。空入力時は `0.0`。
+///
+/// `sample` が `true` なら不偏分散（`n-1` で除する標本分散）、`false` なら母分散（`n` で除する）を返す。
+///
+/// # 計算量
+/// O(N) で 1 パス、数値安定性も高い。
+fn variance<I>(iter: I, sample: bool) -> f64
+where
+    I: IntoIterator<Item = f64>,
+{
+    let mut n = 0f64;
+    let mut mean = 0f64;
+    let mut m2 = 0f64;
+    for x in iter {
+        n += 1.0;
+        let dx = x - mean;
+        mean += dx / n;
+        m2 += dx * (x - mean);
+    }
+    if n == 0.0 {
+        0.0
+    } else {
+        let denom = if sample { (n - 1.0).max(1.0) } else { n };
+        m2 / denom
+    }
+}
+
+/// 標準偏差（`variance` の平方根）を求める。空入力時は `0.0`。
+///
+/// `sample` の意味は `variance` と同じ。
+fn stddev<I>(iter: I, sample: bool) -> f64
+where
+    I: IntoIterator<Item = f64>,
+{
+    variance(iter, sample).sqrt()
+}
+
+/// `Matrix` を行優先順序で埋めながら総和を取る。
+///
+/// 書き込みと読み取りを同じ走査順で行うため、アロケーションとキャッシュ局所性の
+/// 双方の影響を観測できる。
+fn fill_and_sum_row_major_matrix(rows: usize, cols: usize) -> i64 {
+    let mut m = Matrix::filled(rows, cols, 0 as config::DataType);
+    let mut sum = 0i64;
+    for r in 0..rows {
+        for c in 0..cols {
+            m[r][c] = black_box((r * cols + c) as config::DataType);
+            sum += m[r][c] as i64;
+        }
+    }
+    sum
+}
+
+/// `Matrix` を列優先順序で埋めながら総和を取る。
+fn fill_and_sum_col_major_matrix(rows: usize, cols: usize) -> i64 {
+    let mut m = Matrix::filled(rows, cols, 0 as config::DataType);
+    let mut sum = 0i64;
+    for c in 0..cols {
+        for r in 0..rows {
+            m[r][c] = black_box((r * cols + c) as config::DataType);
+            sum += m[r][c] as i64;
+        }
+    }
+    sum
+}
+
+/// `Vec<Vec<DataType>>` を行優先順序で埋めながら総和を取る。
+fn fill_and_sum_row_major_vecvec(rows: usize, cols: usize) -> i64 {
+    let mut v: Vec<Vec<config::DataType>> = vec![vec![0; cols]; rows];
+    let mut sum = 0i64;
+    for (r, row) in v.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            *cell = black_box((r * cols + c) as config::DataType);
+            sum += *cell as i64;
+        }
+    }
+    sum
+}
+
+/// `Vec<Vec<DataType>>` を列優先順序で埋めながら総和を取る。
+fn fill_and_sum_col_major_vecvec(rows: usize, cols: usize) -> i64 {
+    let mut v: Vec<Vec<config::DataType>> = vec![vec![0; cols]; rows];
+    let mut sum = 0i64;
+    for c in 0..cols {
+        for (r, row) in v.iter_mut().enumerate() {
+            row[c] = black_box((r * cols + c) as config::DataType);
+            sum += row[c] as i64;
+        }
+    }
+    sum
+}
+
+/// ラベル付きで `iters` 回サンプリングし、統計的に安定したベンチマーク結果を記録する。
+///
+/// 最初に 1 回ウォームアップを行ってから `iters` 回計測する。各呼び出しは
+/// `black_box` で包んで最適化による消去を防ぐ。単発計測の `ScopeProfiler` より
+/// ノイズに強く、最小値・中央値・平均値・標本標準偏差・標本数を `ctx` に記録し、
+/// `Text` 形式の時はテキストとしても表示する。
+///
+/// # 引数
+/// - `ctx`: 実行コンテキスト（出力形式・結果蓄積先）
+/// - `category`: ケースの分類（例: `data_copy`）
+/// - `container`: コンテナ名（例: `Vec`）
+/// - `label`: 見出し名
+/// - `iters`: ウォームアップを除く計測回数
+/// - `sample_fn`: 計測対象の処理（戻り値は `black_box` に渡され最適化を抑止する）
+fn bench<T>(
+    ctx: &mut RunContext,
+    category: &str,
+    container: &str,
+    label: &str,
+    iters: usize,
+    mut sample_fn: impl FnMut() -> T,
+) {
+    black_box(sample_fn());
+
+    let mut durations_ms: Vec<f64> = Vec::with_capacity(iters);
+    for _ in 0..iters {
+        let start = Instant::now();
+        black_box(sample_fn());
+        durations_ms.push(start.elapsed().as_secs_f64() * 1000.0);
+    }
+
+    let mut sorted = durations_ms.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let min = sorted.first().copied().unwrap_or(0.0);
+    let median = match sorted.len() {
+        0 => 0.0,
+        n if n % 2 == 0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+        n => sorted[n / 2],
+    };
+    let avg = mean(durations_ms.iter().copied());
+    let sd = stddev(durations_ms.iter().copied(), true);
+
+    ctx.logln(format!(
+        "実行時間 ({}): n={} min={:.3}ms median={:.3}ms mean={:.3}ms stddev={:.3}ms",
+        label,
+        durations_ms.len(),
+        min,
+        median,
+        avg,
+        sd
+    ));
+
+    ctx.records.push(BenchRecord {
+        category: category.to_string(),
+        container: container.to_string(),
+        label: label.to_string(),
+        samples: durations_ms.len(),
+        min_ms: min,
+        median_ms: median,
+        mean_ms: avg,
+        stddev_ms: sd,
+    });
+}
+
+/// イテレータのシーケンシャル読み取りを `repeats` 回行い、合計値を返す。
+///
+/// 最適化抑止は行わず、素直に加算するのみ。
+fn read_sequential<I>(it: I, repeats: usize) -> i64
+where
+    I: Clone + IntoIterator<Item = i32>,
+{
+    std::iter::repeat(())
+        .take(repeats)
+        .map(|_| it.clone().into_iter().map(|v| v as i64).sum::<i64>())
+        .sum()
+}
+
+/// Fisher–Yates 法で `0..n` の順列をシャッフルする（末尾から先頭へ向かう標準手順）。
+///
+/// 要素 `i` を `0..=i` から一様に選んだ添字と交換することで、各順列が等確率になる。
+fn fisher_yates_shuffle<R: RandomSource + ?Sized>(rng: &mut R, n: usize) -> Vec<usize> {
+    let mut perm: Vec<usize> = (0..n).collect();
+    for i in (1..n).rev() {
+        let j = rng.next_i32_range(0, i as i32) as usize;
+        perm.swap(i, j);
+    }
+    perm
+}
+
+/// 添字アクセス可能なコンテナを、シャッフル済みの順列 `perm` の順序で `repeats` 回読み取る。
+///
+/// シーケンシャル読み取りとは対照的に、ポインタ追跡やキャッシュミスの影響を観測できる。
+fn read_random<A>(container: &A, perm: &[usize], repeats: usize) -> i64
+where
+    A: Index<usize, Output = config::DataType> + ?Sized,
+{
+    std::iter::repeat(())
+        .take(repeats)
+        .map(|_| perm.iter().map(|&i| container[i] as i64).sum::<i64>())
+        .sum()
+}
+
+/// ベンチマーク本体。各処理を独立に測定・出力する。
+fn run(format: OutputFormat) {
+    use config::*;
+
+    let mut ctx = RunContext::new(format);
+
+    ctx.logln("===== Rust コンテナ・ベンチマーク =====");
+    ctx.logln(format!("要素数: {}\n", ELEMENT_COUNT));
+
+    // 元データ生成
+    ctx.logln("● 元データ生成");
+    let src = generate_source(ELEMENT_COUNT, RANDOM_MIN, RANDOM_MAX, format == OutputFormat::Text);
+
+    // --- 乱数生成器の比較（バックエンドによる生成コストの違い） ---
+    ctx.logln("\n● 乱数生成器の比較");
+    {
+        let _profiler = ScopeProfiler::with_verbosity("Mt19937_配列生成", format == OutputFormat::Text);
+        let mut rng = Mt19937::new(0xDEAD_BEEF);
+        let v = generate_with(&mut rng, ELEMENT_COUNT, RANDOM_MIN, RANDOM_MAX);
+        black_box(v);
+    }
+    {
+        let _profiler =
+            ScopeProfiler::with_verbosity("Xoshiro256StarStar_配列生成", format == OutputFormat::Text);
+        let mut rng = Xoshiro256StarStar::new(0xDEAD_BEEF);
+        let v = generate_with(&mut rng, ELEMENT_COUNT, RANDOM_MIN, RANDOM_MAX);
+        black_box(v);
+    }
+
+    // --- データコピー性能（ケースごとに新しいコンテナを生成） ---
+    ctx.logln(format!("\n● データコピー性能 ({}回計測)", BENCH_ITERS));
+    bench(&mut ctx, "data_copy", "Vec", "Vec_reserveなし", BENCH_ITERS, || {
+        let mut v: Vec<DataType> = Vec::new();
+        v.extend_from_slice(&src);
+        v
+    });
+    bench(&mut ctx, "data_copy", "Vec", "Vec_reserveあり", BENCH_ITERS, || {
+        let mut v: Vec<DataType> = Vec::with_capacity(ELEMENT_COUNT);
+        v.extend_from_slice(&src);
+        v
+    });
+    bench(&mut ctx, "data_copy", "VecDeque", "VecDeque", BENCH_ITERS, || {
+        let mut d: VecDeque<DataType> = VecDeque::with_capacity(ELEMENT_COUNT);
+        d.extend(src.iter().copied());
+        d
+    });
+    bench(&mut ctx, "data_copy", "LinkedList", "LinkedList", BENCH_ITERS, || {
+        let mut l: LinkedList<DataType> = LinkedList::new();
+        l.extend(src.iter().copied());
+        l
+    });
+
+    // --- 以降の処理（読み取り・統計）用に、計測対象外でコンテナを準備 ---
+    let vec_main: Vec<DataType> = src.clone();
+    let deq_main: VecDeque<DataType> = src.iter().copied().collect();
+    let lis_main: LinkedList<DataType> = src.iter().copied().collect();
+
+    // --- シーケンシャル読み取り ---
+    ctx.logln(format!(
+        "\n● シーケンシャル読み取り性能 ({}回繰り返し, {}回計測)",
+        READ_REPEAT_COUNT, BENCH_ITERS
+    ));
+    bench(&mut ctx, "read_sequential", "Vec", "Vec", BENCH_ITERS, || {
+        read_sequential(vec_main.iter().copied(), READ_REPEAT_COUNT)
+    });
+    bench(&mut ctx, "read_sequential", "VecDeque", "VecDeque", BENCH_ITERS, || {
+        read_sequential(deq_main.iter().copied(), READ_REPEAT_COUNT)
+    });
+    bench(&mut ctx, "read_sequential", "LinkedList", "LinkedList", BENCH_ITERS, || {
+        read_sequential(lis_main.iter().copied(), READ_REPEAT_COUNT)
+    });
+
+    // --- ランダムアクセス読み取り（ポインタ追跡・非連続レイアウトの影響） ---
+    ctx.logln(format!(
+        "\n● ランダムアクセス読み取り性能 ({}回繰り返し, {}回計測)",
+        READ_REPEAT_COUNT, BENCH_ITERS
+    ));
+    let shuffle_seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let mut shuffle_rng = Mt19937::new(shuffle_seed);
+    let perm = fisher_yates_shuffle(&mut shuffle_rng, ELEMENT_COUNT);
+    bench(&mut ctx, "read_random", "Vec", "Vec", BENCH_ITERS, || {
+        read_random(&vec_main, &perm, READ_REPEAT_COUNT)
+    });
+    bench(&mut ctx, "read_random", "VecDeque", "VecDeque", BENCH_ITERS, || {
+        read_random(&deq_main, &perm, READ_REPEAT_COUNT)
+    });
+    ctx.logln("実行時間 (LinkedList): 対象外（O(1) 添字アクセスができないため計測をスキップ）");
+
+    // --- 先頭確認 ---
+    if format == OutputFormat::Text {
+        println!("\n● 先頭 {} 要素の確認", DISPLAY_COUNT);
+        print_first_n("Vec", vec_main.iter().copied(), DISPLAY_COUNT);
+        print_first_n("VecDeque", deq_main.iter().copied(), DISPLAY_COUNT);
+        print_first_n("LinkedList", lis_main.iter().copied(), DISPLAY_COUNT);
+    }
+
+    // --- 平均 ---
+    ctx.logln(format!("\n● 平均値計算の性能 ({}回計測)", BENCH_ITERS));
+    ctx.logln(format!("Vecの平均値: {:.3}", mean(vec_main.iter().map(|&v| v as f64))));
+    bench(&mut ctx, "mean", "Vec", "Vec_平均値", BENCH_ITERS, || mean(vec_main.iter().map(|&v| v as f64)));
+    ctx.logln(format!("VecDequeの平均値: {:.3}", mean(deq_main.iter().map(|&v| v as f64))));
+    bench(&mut ctx, "mean", "VecDeque", "VecDeque_平均値", BENCH_ITERS, || {
+        mean(deq_main.iter().map(|&v| v as f64))
+    });
+    ctx.logln(format!("LinkedListの平均値: {:.3}", mean(lis_main.iter().map(|&v| v as f64))));
+    bench(&mut ctx, "mean", "LinkedList", "LinkedList_平均値", BENCH_ITERS, || {
+        mean(lis_main.iter().map(|&v| v as f64))
+    });
+
+    // --- 分散（母分散を表示しつつ、計測自体には標本標準偏差を併用） ---
+    ctx.logln(format!("\n● 分散計算の性能 ({}回計測)", BENCH_ITERS));
+    ctx.logln(format!("Vecの分散: {:.1}", variance(vec_main.iter().map(|&v| v as f64), false)));
+    bench(&mut ctx, "variance", "Vec", "Vec_分散", BENCH_ITERS, || {
+        variance(vec_main.iter().map(|&v| v as f64), false)
+    });
+    ctx.logln(format!("VecDequeの分散: {:.1}", variance(deq_main.iter().map(|&v| v as f64), false)));
+    bench(&mut ctx, "variance", "VecDeque", "VecDeque_分散", BENCH_ITERS, || {
+        variance(deq_main.iter().map(|&v| v as f64), false)
+    });
+    ctx.logln(format!("LinkedListの分散: {:.1}", variance(lis_main.iter().map(|&v| v as f64), false)));
+    bench(&mut ctx, "variance", "LinkedList", "LinkedList_分散", BENCH_ITERS, || {
+        variance(lis_main.iter().map(|&v| v as f64), false)
+    });
+
+    // --- 連想コンテナ（キー順序による挿入・検索性能の違い） ---
+    ctx.logln(format!("\n● 連想コンテナ性能 ({}回計測)", BENCH_ITERS));
+    let keys_random = generate_keys_random(ELEMENT_COUNT);
+    let keys_sequential = generate_keys_sequential(ELEMENT_COUNT);
+
+    bench(&mut ctx, "map_insert", "BTreeMap", "BTreeMap_挿入_ランダム順", BENCH_ITERS, || {
+        let m: BTreeMap<DataType, DataType> = keys_random.iter().map(|&k| (k, k)).collect();
+        m
+    });
+    bench(&mut ctx, "map_insert", "BTreeMap", "BTreeMap_挿入_昇順", BENCH_ITERS, || {
+        let m: BTreeMap<DataType, DataType> = keys_sequential.iter().map(|&k| (k, k)).collect();
+        m
+    });
+    bench(&mut ctx, "map_insert", "HashMap", "HashMap_挿入_ランダム順", BENCH_ITERS, || {
+        let mut m: HashMap<DataType, DataType> = HashMap::with_capacity(ELEMENT_COUNT);
+        m.extend(keys_random.iter().map(|&k| (k, k)));
+        m
+    });
+    bench(&mut ctx, "map_insert", "HashMap", "HashMap_挿入_昇順", BENCH_ITERS, || {
+        let mut m: HashMap<DataType, DataType> = HashMap::with_capacity(ELEMENT_COUNT);
+        m.extend(keys_sequential.iter().map(|&k| (k, k)));
+        m
+    });
+
+    // --- 検索（計測対象外であらかじめ挿入済みのコンテナを用意） ---
+    let btree_random: BTreeMap<DataType, DataType> = keys_random.iter().map(|&k| (k, k)).collect();
+    let btree_sequential: BTreeMap<DataType, DataType> = keys_sequential.iter().map(|&k| (k, k)).collect();
+    let hash_random: HashMap<DataType, DataType> = keys_random.iter().map(|&k| (k, k)).collect();
+    let hash_sequential: HashMap<DataType, DataType> = keys_sequential.iter().map(|&k| (k, k)).collect();
+
+    bench(&mut ctx, "map_lookup", "BTreeMap", "BTreeMap_検索_ランダム順", BENCH_ITERS, || {
+        keys_random.iter().filter_map(|k| btree_random.get(k)).map(|&v| v as i64).sum::<i64>()
+    });
+    bench(&mut ctx, "map_lookup", "BTreeMap", "BTreeMap_検索_昇順", BENCH_ITERS, || {
+        keys_sequential.iter().filter_map(|k| btree_sequential.get(k)).map(|&v| v as i64).sum::<i64>()
+    });
+    bench(&mut ctx, "map_lookup", "HashMap", "HashMap_検索_ランダム順", BENCH_ITERS, || {
+        keys_random.iter().filter_map(|k| hash_random.get(k)).map(|&v| v as i64).sum::<i64>()
+    });
+    bench(&mut ctx, "map_lookup", "HashMap", "HashMap_検索_昇順", BENCH_ITERS, || {
+        keys_sequential.iter().filter_map(|k| hash_sequential.get(k)).map(|&v| v as i64).sum::<i64>()
+    });
+
+    // --- 2次元走査のキャッシュ局所性（Matrix vs Vec<Vec<T>>） ---
+    ctx.logln(format!(
+        "\n● 2次元走査性能 ({}x{}, {}回計測)",
+        MATRIX_ROWS, MATRIX_COLS, BENCH_ITERS
+    ));
+    bench(&mut ctx, "matrix_2d", "Matrix", "Matrix_行優先", BENCH_ITERS, || {
+        fill_and_sum_row_major_matrix(MATRIX_ROWS, MATRIX_COLS)
+    });
+    bench(&mut ctx, "matrix_2d", "Matrix", "Matrix_列優先", BENCH_ITERS, || {
+        fill_and_sum_col_major_matrix(MATRIX_ROWS, MATRIX_COLS)
+    });
+    bench(&mut ctx, "matrix_2d", "VecVec", "VecVec_行優先", BENCH_ITERS, || {
+        fill_and_sum_row_major_vecvec(MATRIX_ROWS, MATRIX_COLS)
+    });
+    bench(&mut ctx, "matrix_2d", "VecVec", "VecVec_列優先", BENCH_ITERS, || {
+        fill_and_sum_col_major_vecvec(MATRIX_ROWS, MATRIX_COLS)
+    });
+
+    ctx.logln("\n===== ベンチマーク終了 =====");
+    ctx.emit_records();
+}
+
+/// エントリポイント。
+fn main() {
+    let format = parse_output_format();
+    let _profiler = ScopeProfiler::with_verbosity("全体処理", format == OutputFormat::Text);
+    run(format);
+}